@@ -1,8 +1,9 @@
 pub mod sha3;
 
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use derive_more::{
     self,
-    derive::{AsRef, Display, From},
+    derive::{AsRef, Display, Error, From},
 };
 
 /// A hash algorithm with the given fixed output size.
@@ -13,13 +14,33 @@ where
     /// The output size of this hash algorithm.
     const OUTPUT_SIZE: usize = N;
 
+    /// The domain separation prefix for leaf hashes, following RFC 6962.
+    const LEAF_PREFIX: u8 = 0x00;
+
+    /// The domain separation prefix for internal node hashes, following RFC 6962.
+    const NODE_PREFIX: u8 = 0x01;
+
     /// Calculate the hash value for the given value which can be referenced as bytes.
     fn hash(&self, value: impl AsRef<[u8]>) -> Hash<N>;
 
-    /// Calculate the hash value of the concatenation of the two given hash values.
+    /// Calculate the hash value for the given leaf value, prepending [`Self::LEAF_PREFIX`]
+    /// for domain separation against internal nodes (RFC 6962).
+    fn hash_leaf(&self, value: impl AsRef<[u8]>) -> Hash<N> {
+        let value = value.as_ref();
+
+        let mut bytes = Vec::with_capacity(1 + value.len());
+        bytes.push(Self::LEAF_PREFIX);
+        bytes.extend(value);
+
+        self.hash(bytes)
+    }
+
+    /// Calculate the hash value of the concatenation of the two given hash values, prepending
+    /// [`Self::NODE_PREFIX`] for domain separation against leaves (RFC 6962).
     fn concat_hashes(&self, left: Hash<N>, right: Hash<N>) -> Hash<N> {
-        let mut value = Vec::with_capacity(2 * N);
+        let mut value = Vec::with_capacity(1 + 2 * N);
 
+        value.push(Self::NODE_PREFIX);
         value.extend(left.0);
         value.extend(right.0);
 
@@ -33,9 +54,85 @@ where
 #[display("0x{}", const_hex::encode(_0))]
 pub struct Hash<const N: usize>(pub [u8; N]);
 
+impl<const N: usize> Hash<N> {
+    /// Parse a hash from its hex encoding, with or without a leading `0x`, complementing the
+    /// [`Display`](std::fmt::Display) encoding. The decoded length must equal `N`.
+    pub fn from_hex(s: &str) -> Result<Self, ParseError> {
+        Self::from_bytes(const_hex::decode(s)?)
+    }
+
+    /// Parse a hash from its standard base64 encoding. The decoded length must equal `N`.
+    pub fn from_base64(s: &str) -> Result<Self, ParseError> {
+        Self::from_bytes(BASE64_STANDARD.decode(s)?)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, ParseError> {
+        let actual = bytes.len();
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| ParseError::Length { expected: N, actual })?;
+        Ok(Hash(bytes))
+    }
+}
+
+/// An error parsing a [`Hash`] from its textual encoding.
+#[derive(Debug, Display, Error, From)]
+pub enum ParseError {
+    /// The input was not valid hex.
+    #[display("invalid hex encoding")]
+    Hex(const_hex::FromHexError),
+
+    /// The input was not valid base64.
+    #[display("invalid base64 encoding")]
+    Base64(base64::DecodeError),
+
+    /// The decoded byte length did not match the hash size.
+    #[from(ignore)]
+    #[display("invalid length: expected {expected} bytes, got {actual}")]
+    Length { expected: usize, actual: usize },
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Hash<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Hash<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        if deserializer.is_human_readable() {
+            // Use an owned `String` rather than a borrowed `&str` so non-borrowing deserializers
+            // (e.g. `serde_json::from_reader`) also work.
+            let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+            Hash::from_hex(&s).map_err(D::Error::custom)
+        } else {
+            let bytes = <Vec<u8> as serde::Deserialize>::deserialize(deserializer)?;
+            let len = bytes.len();
+            let bytes = bytes
+                .try_into()
+                .map_err(|_| D::Error::invalid_length(len, &"N bytes"))?;
+            Ok(Hash(bytes))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Hash;
+    use crate::{hash::ParseError, Hash};
 
     #[test]
     fn test_hash_derive() {
@@ -44,4 +141,43 @@ mod tests {
         assert_eq!(format!("{hash:?}"), "Hash([0, 1, 2, 3])");
         assert_eq!(format!("{hash}"), "0x00010203");
     }
+
+    #[test]
+    fn test_from_hex() {
+        let hash = Hash::<4>::from([0, 1, 2, 3]);
+
+        // Round-trips through the `Display` encoding, with or without the `0x` prefix.
+        assert_eq!(Hash::<4>::from_hex(&hash.to_string()).unwrap(), hash);
+        assert_eq!(Hash::<4>::from_hex("00010203").unwrap(), hash);
+
+        assert!(matches!(
+            Hash::<4>::from_hex("0x000102"),
+            Err(ParseError::Length {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            Hash::<4>::from_hex("0xzz010203"),
+            Err(ParseError::Hex(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_base64() {
+        let hash = Hash::<4>::from([0, 1, 2, 3]);
+
+        assert_eq!(Hash::<4>::from_base64("AAECAw==").unwrap(), hash);
+        assert!(matches!(
+            Hash::<4>::from_base64("AAEC"),
+            Err(ParseError::Length {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            Hash::<4>::from_base64("!!!!"),
+            Err(ParseError::Base64(_))
+        ));
+    }
 }
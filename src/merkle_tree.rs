@@ -1,16 +1,17 @@
 use crate::{Hash, Hasher};
-use std::iter;
+use std::{collections::HashMap, iter};
 
 /// A perfect (padded) Merkle tree using a hash algorithm with the given fixed output size.
 #[derive(Debug, Clone)]
 pub struct MerkleTree<const N: usize> {
     nodes: Vec<Hash<N>>,
     leaf_count: usize,
+    item_count: usize,
 }
 
 impl<const N: usize> MerkleTree<N> {
     pub fn new(items: &[impl AsRef<[u8]>], hasher: impl Hasher<N>) -> Self {
-        let Some(last_hash) = items.last().map(|item| hasher.hash(item)) else {
+        let Some(last_hash) = items.last().map(|item| hasher.hash_leaf(item)) else {
             panic!("Merkle tree must not be empty");
         };
 
@@ -19,7 +20,7 @@ impl<const N: usize> MerkleTree<N> {
         let mut nodes = Vec::with_capacity(2 * leaf_count - 1);
         let leaves = items
             .iter()
-            .map(|item| hasher.hash(item))
+            .map(|item| hasher.hash_leaf(item))
             .chain(iter::repeat(last_hash))
             .take(leaf_count);
         nodes.extend(leaves);
@@ -41,35 +42,79 @@ impl<const N: usize> MerkleTree<N> {
             level_len /= 2;
         }
 
-        Self { nodes, leaf_count }
+        Self {
+            nodes,
+            leaf_count,
+            item_count: items.len(),
+        }
+    }
+
+    /// Build a non-padded tree for an arbitrary number of items.
+    ///
+    /// Each level of length `n` yields a level of `(n + 1) / 2` nodes; when a level has an odd
+    /// count the lone trailing node is promoted to the parent level unchanged instead of being
+    /// hashed against a duplicate of itself. This avoids the padding overhead and the
+    /// duplicate-leaf malleability of [`new`](Self::new), yielding a canonical root for any item
+    /// count.
+    pub fn new_unbalanced(items: &[impl AsRef<[u8]>], hasher: impl Hasher<N>) -> Self {
+        assert!(!items.is_empty(), "Merkle tree must not be empty");
+
+        let leaf_count = items.len();
+
+        let mut nodes = Vec::with_capacity(2 * leaf_count);
+        nodes.extend(items.iter().map(|item| hasher.hash_leaf(item)));
+
+        let mut start = 0;
+        let mut len = leaf_count;
+        while len > 1 {
+            let end = start + len;
+            let parents = nodes[start..end]
+                .chunks(2)
+                .map(|chunk| match chunk {
+                    [left, right] => hasher.concat_hashes(*left, *right),
+                    [lone] => *lone,
+                    _ => unreachable!("chunks(2) yields one or two elements"),
+                })
+                .collect::<Vec<_>>();
+            nodes.extend(parents);
+
+            start = end;
+            len = (len + 1) / 2;
+        }
+
+        Self {
+            nodes,
+            leaf_count,
+            item_count: items.len(),
+        }
     }
 
     pub fn root(&self) -> Hash<N> {
         *self.nodes.last().unwrap()
     }
 
-    pub fn proof(&self, mut index: usize) -> MerkleProof<N> {
+    pub fn proof(&self, index: usize) -> MerkleProof<N> {
         assert!(
             index < self.leaf_count,
             "index must be within number of leaf nodes"
         );
 
-        let path_len = self.leaf_count.trailing_zeros() as usize;
-        let mut path = Vec::with_capacity(path_len);
-
-        let mut prev_level_len = 0;
-        let mut level_len = self.leaf_count;
-        while level_len > 1 {
-            let position = if index % 2 == 0 {
-                PositionedHash::Right(self.nodes[index + 1])
+        let levels = self.levels();
+        let mut path = Vec::with_capacity(levels.len() - 1);
+
+        // Walk up level by level, recording the sibling at each step. A lone trailing node of an
+        // odd level is promoted unchanged and has no sibling, so that step is skipped.
+        let mut index = index;
+        for &(start, len) in &levels[..levels.len() - 1] {
+            if index % 2 == 0 {
+                if index + 1 < len {
+                    path.push(PositionedHash::Right(self.nodes[start + index + 1]));
+                }
             } else {
-                PositionedHash::Left(self.nodes[index - 1])
-            };
-            path.push(position);
+                path.push(PositionedHash::Left(self.nodes[start + index - 1]));
+            }
 
-            index = index + level_len - (index - prev_level_len + 1) / 2;
-            prev_level_len = level_len;
-            level_len /= 2;
+            index /= 2;
         }
 
         MerkleProof {
@@ -77,9 +122,82 @@ impl<const N: usize> MerkleTree<N> {
             path,
         }
     }
+
+    /// The `(start, len)` of every level, from the leaves up to and including the single-node
+    /// root level. Works for both the padded [`new`](Self::new) and the unbalanced
+    /// [`new_unbalanced`](Self::new_unbalanced) layout.
+    fn levels(&self) -> Vec<(usize, usize)> {
+        let mut levels = Vec::new();
+        let mut start = 0;
+        let mut len = self.leaf_count;
+        loop {
+            levels.push((start, len));
+            if len == 1 {
+                break;
+            }
+            start += len;
+            len = (len + 1) / 2;
+        }
+        levels
+    }
+
+    /// Replace the leaf at `index` with `item` and recompute only the `O(log leaf_count)` nodes
+    /// on the path from that leaf to the root, returning the new root.
+    ///
+    /// This avoids a full rebuild when a single leaf of an already built tree changes. It walks
+    /// the same level structure as [`proof`](Self::proof), carrying a promoted sibling-less node
+    /// up unchanged.
+    ///
+    /// Only valid for trees without padding, i.e. those built with
+    /// [`new_unbalanced`](Self::new_unbalanced) or with [`new`](Self::new) over a power-of-two
+    /// item count. A padded [`new`](Self::new) tree repeats its last leaf across the padding
+    /// positions, which a single-path update could not keep in sync; calling `update_leaf` on
+    /// such a tree panics.
+    pub fn update_leaf(
+        &mut self,
+        index: usize,
+        item: impl AsRef<[u8]>,
+        hasher: &impl Hasher<N>,
+    ) -> Hash<N> {
+        assert!(
+            self.leaf_count == self.item_count,
+            "update_leaf requires a tree without padding; use new_unbalanced or a power-of-two item count"
+        );
+        assert!(
+            index < self.leaf_count,
+            "index must be within number of leaf nodes"
+        );
+
+        self.nodes[index] = hasher.hash_leaf(item);
+
+        let levels = self.levels();
+        let mut index = index;
+        for &(start, len) in &levels[..levels.len() - 1] {
+            let end = start + len;
+            let local = index - start;
+
+            let node = if local % 2 == 0 {
+                if local + 1 < len {
+                    hasher.concat_hashes(self.nodes[index], self.nodes[index + 1])
+                } else {
+                    // Lone trailing node of an odd level: promoted unchanged.
+                    self.nodes[index]
+                }
+            } else {
+                hasher.concat_hashes(self.nodes[index - 1], self.nodes[index])
+            };
+
+            let parent = end + local / 2;
+            self.nodes[parent] = node;
+            index = parent;
+        }
+
+        self.root()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MerkleProof<const N: usize> {
     root: Hash<N>,
     path: Vec<PositionedHash<N>>,
@@ -87,7 +205,7 @@ pub struct MerkleProof<const N: usize> {
 
 impl<const N: usize> MerkleProof<N> {
     pub fn validate(&self, item: &(impl AsRef<[u8]> + ?Sized), hasher: &impl Hasher<N>) -> bool {
-        let mut hash = hasher.hash(item);
+        let mut hash = hasher.hash_leaf(item);
 
         for positioned_hash in &self.path {
             match positioned_hash {
@@ -101,14 +219,307 @@ impl<const N: usize> MerkleProof<N> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PositionedHash<const N: usize> {
     Left(Hash<N>),
     Right(Hash<N>),
 }
 
+/// An append-only Merkle tree of fixed `depth` that maintains only the minimal frontier.
+///
+/// Appending assigns the next unused leaf and advances in `O(depth)` time (plus `O(depth)` per
+/// witnessed leaf) and space: one
+/// "filled" hash per level holds the left sibling that is still waiting for its right partner.
+/// Empty right subtrees collapse to precomputed per-level zero hashes, so [`root`](Self::root)
+/// is well-defined before the tree is full. Individual leaves can be [`witness`](Self::witness)ed,
+/// after which their authentication path is kept up to date and can be turned into a
+/// [`MerkleProof`] at any later time via [`path`](Self::path).
+#[derive(Debug, Clone)]
+pub struct IncrementalMerkleTree<const N: usize> {
+    depth: usize,
+    zeros: Vec<Hash<N>>,
+    filled: Vec<Hash<N>>,
+    witnesses: Vec<Witness<N>>,
+    root: Hash<N>,
+    next_index: usize,
+}
+
+impl<const N: usize> IncrementalMerkleTree<N> {
+    /// Create an empty tree of the given `depth`, holding up to `2^depth` leaves.
+    pub fn new(depth: usize, hasher: impl Hasher<N>) -> Self {
+        let mut zeros = Vec::with_capacity(depth + 1);
+        zeros.push(hasher.hash_leaf([]));
+        for level in 0..depth {
+            let zero = hasher.concat_hashes(zeros[level], zeros[level]);
+            zeros.push(zero);
+        }
+
+        let filled = zeros[..depth].to_vec();
+        let root = zeros[depth];
+
+        Self {
+            depth,
+            zeros,
+            filled,
+            witnesses: Vec::new(),
+            root,
+            next_index: 0,
+        }
+    }
+
+    /// Assign `item` to the next unused leaf, advance, and return its leaf id.
+    ///
+    /// Panics if the tree is already full.
+    pub fn append(&mut self, item: impl AsRef<[u8]>, hasher: &impl Hasher<N>) -> usize {
+        assert!(
+            self.next_index < (1 << self.depth),
+            "incremental Merkle tree is full"
+        );
+
+        let id = self.next_index;
+        let mut current = hasher.hash_leaf(item);
+
+        let mut index = id;
+        for level in 0..self.depth {
+            // `current` is the node at (level, index) as of this append, with still-empty parts
+            // at their zero hashes; offer it to every witness whose authentication path has
+            // this position as a sibling. Later appends into the same subtree overwrite it.
+            for witness in &mut self.witnesses {
+                if (witness.id >> level) ^ 1 == index {
+                    witness.path[level] = current;
+                }
+            }
+
+            if index % 2 == 0 {
+                self.filled[level] = current;
+                current = hasher.concat_hashes(current, self.zeros[level]);
+            } else {
+                current = hasher.concat_hashes(self.filled[level], current);
+            }
+
+            index /= 2;
+        }
+
+        self.root = current;
+        self.next_index += 1;
+        id
+    }
+
+    /// The current root, including the precomputed zero hashes for all still-empty subtrees.
+    pub fn root(&self) -> Hash<N> {
+        self.root
+    }
+
+    /// The number of leaves appended so far.
+    pub fn len(&self) -> usize {
+        self.next_index
+    }
+
+    /// Whether no leaf has been appended yet.
+    pub fn is_empty(&self) -> bool {
+        self.next_index == 0
+    }
+
+    /// Start retaining the authentication path of the most recently appended leaf and return
+    /// its id. Subsequent appends keep the retained path up to date.
+    ///
+    /// Panics if no leaf has been appended yet.
+    pub fn witness(&mut self) -> usize {
+        assert!(
+            self.next_index > 0,
+            "cannot witness before appending a leaf"
+        );
+
+        let id = self.next_index - 1;
+
+        let mut path = self.zeros[..self.depth].to_vec();
+        for (level, sibling) in path.iter_mut().enumerate() {
+            // Left siblings are already finalized and live in `filled`; right siblings are in
+            // the future and stay at their zero value until a later append fills them in.
+            if (id >> level) % 2 == 1 {
+                *sibling = self.filled[level];
+            }
+        }
+
+        self.witnesses.push(Witness { id, path });
+        id
+    }
+
+    /// Produce a [`MerkleProof`] for a previously [`witness`](Self::witness)ed leaf.
+    ///
+    /// Panics if `id` is not currently witnessed.
+    pub fn path(&self, id: usize) -> MerkleProof<N> {
+        let witness = self
+            .witnesses
+            .iter()
+            .find(|witness| witness.id == id)
+            .expect("id must be witnessed");
+
+        let path = witness
+            .path
+            .iter()
+            .enumerate()
+            .map(|(level, &sibling)| {
+                if (id >> level) % 2 == 0 {
+                    PositionedHash::Right(sibling)
+                } else {
+                    PositionedHash::Left(sibling)
+                }
+            })
+            .collect();
+
+        MerkleProof {
+            root: self.root,
+            path,
+        }
+    }
+
+    /// Stop retaining the authentication path of `id`, pruning the siblings held for it.
+    pub fn remove_witness(&mut self, id: usize) {
+        self.witnesses.retain(|witness| witness.id != id);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Witness<const N: usize> {
+    id: usize,
+    path: Vec<Hash<N>>,
+}
+
+/// A sparse Merkle tree keyed by the `N * 8` bit path of `hasher.hash(key)`, able to prove both
+/// that a key maps to a value (membership) and that a key is absent (non-membership).
+///
+/// Only the nodes that diverge from empty are materialized; every all-empty subtree at height
+/// `h` collapses to the precomputed `zeros[h]`, so the full `2^(N * 8)` leaves are never stored.
+/// A non-membership proof is a membership proof whose leaf resolves to `zeros[0]` (the hash of
+/// an empty leaf) and is checked with [`MerkleProof::validate`] against an empty item.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleTree<const N: usize> {
+    nodes: HashMap<(usize, [u8; N]), Hash<N>>,
+    zeros: Vec<Hash<N>>,
+    root: Hash<N>,
+}
+
+impl<const N: usize> SparseMerkleTree<N> {
+    /// The depth of the tree, i.e. the number of key-path bits.
+    const DEPTH: usize = N * 8;
+
+    /// Create an empty sparse Merkle tree.
+    pub fn new(hasher: impl Hasher<N>) -> Self {
+        let mut zeros = Vec::with_capacity(Self::DEPTH + 1);
+        zeros.push(hasher.hash_leaf([]));
+        for height in 0..Self::DEPTH {
+            let zero = hasher.concat_hashes(zeros[height], zeros[height]);
+            zeros.push(zero);
+        }
+
+        let root = zeros[Self::DEPTH];
+
+        Self {
+            nodes: HashMap::new(),
+            zeros,
+            root,
+        }
+    }
+
+    /// Map `key` to `value`, lazily creating only the nodes that diverge from empty.
+    pub fn insert(
+        &mut self,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+        hasher: &impl Hasher<N>,
+    ) {
+        let path = hasher.hash(key).0;
+
+        let mut node = hasher.hash_leaf(value);
+        self.nodes.insert((Self::DEPTH, mask(&path, Self::DEPTH)), node);
+
+        for depth in (1..=Self::DEPTH).rev() {
+            let height = Self::DEPTH - depth;
+            let sibling = self.node(depth, flip_bit(&path, depth - 1), height);
+
+            node = if bit(&path, depth - 1) == 0 {
+                hasher.concat_hashes(node, sibling)
+            } else {
+                hasher.concat_hashes(sibling, node)
+            };
+
+            self.nodes.insert((depth - 1, mask(&path, depth - 1)), node);
+        }
+
+        self.root = node;
+    }
+
+    /// The current root, collapsing every empty subtree to its precomputed zero hash.
+    pub fn root(&self) -> Hash<N> {
+        self.root
+    }
+
+    /// Return the sibling hashes along `key`'s path as a [`MerkleProof`].
+    ///
+    /// Validate it with [`MerkleProof::validate`] against the value for a membership proof, or
+    /// against an empty item for a non-membership proof.
+    pub fn proof(&self, key: impl AsRef<[u8]>, hasher: &impl Hasher<N>) -> MerkleProof<N> {
+        let path = hasher.hash(key).0;
+
+        let mut siblings = Vec::with_capacity(Self::DEPTH);
+        for depth in (1..=Self::DEPTH).rev() {
+            let height = Self::DEPTH - depth;
+            let sibling = self.node(depth, flip_bit(&path, depth - 1), height);
+
+            let positioned = if bit(&path, depth - 1) == 0 {
+                PositionedHash::Right(sibling)
+            } else {
+                PositionedHash::Left(sibling)
+            };
+            siblings.push(positioned);
+        }
+
+        MerkleProof {
+            root: self.root,
+            path: siblings,
+        }
+    }
+
+    /// The stored hash of the node at `depth` addressed by `path`, or the empty-subtree hash at
+    /// `height` if that subtree has never been materialized.
+    fn node(&self, depth: usize, path: [u8; N], height: usize) -> Hash<N> {
+        self.nodes
+            .get(&(depth, mask(&path, depth)))
+            .copied()
+            .unwrap_or(self.zeros[height])
+    }
+}
+
+/// The `i`-th bit of `path`, counted from the most significant bit.
+fn bit<const N: usize>(path: &[u8; N], i: usize) -> u8 {
+    (path[i / 8] >> (7 - i % 8)) & 1
+}
+
+/// A copy of `path` with its `i`-th bit (from the most significant) flipped.
+fn flip_bit<const N: usize>(path: &[u8; N], i: usize) -> [u8; N] {
+    let mut path = *path;
+    path[i / 8] ^= 1 << (7 - i % 8);
+    path
+}
+
+/// A copy of `path` with all bits from index `depth` onwards cleared, canonicalizing the
+/// address of a node at that depth.
+fn mask<const N: usize>(path: &[u8; N], depth: usize) -> [u8; N] {
+    let mut path = *path;
+    for i in depth..N * 8 {
+        path[i / 8] &= !(1 << (7 - i % 8));
+    }
+    path
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{sha3::Sha3Hasher, Hasher, MerkleProof, MerkleTree, PositionedHash};
+    use crate::{
+        merkle_tree::{IncrementalMerkleTree, SparseMerkleTree},
+        sha3::Sha3Hasher,
+        Hasher, MerkleProof, MerkleTree, PositionedHash,
+    };
 
     #[test]
     #[should_panic]
@@ -121,7 +532,7 @@ mod tests {
         let hasher = Sha3Hasher;
 
         let value = "one";
-        let hash = hasher.hash(value);
+        let hash = hasher.hash_leaf(value);
         let tree = MerkleTree::new(&[value], hasher);
 
         let root_hash = tree.root();
@@ -134,8 +545,8 @@ mod tests {
 
         let one = "one";
         let two = "two";
-        let one_hash = hasher.hash(one);
-        let two_hash = hasher.hash(two);
+        let one_hash = hasher.hash_leaf(one);
+        let two_hash = hasher.hash_leaf(two);
         let tree = MerkleTree::new(&[one, two], hasher);
 
         let root_hash = tree.root();
@@ -150,9 +561,9 @@ mod tests {
         let one = "one";
         let two = "two";
         let three = "three";
-        let one_hash = hasher.hash(one);
-        let two_hash = hasher.hash(two);
-        let three_hash = hasher.hash(three);
+        let one_hash = hasher.hash_leaf(one);
+        let two_hash = hasher.hash_leaf(two);
+        let three_hash = hasher.hash_leaf(three);
         let tree = MerkleTree::new(&[one, two, three], hasher);
 
         let root_hash = tree.root();
@@ -170,18 +581,18 @@ mod tests {
 
         let items = (0..8).map(|n| n.to_string()).collect::<Vec<_>>();
 
-        let hash_0 = hasher.hash(&items[0]);
-        let hash_1 = hasher.hash(&items[1]);
-        let hash_2 = hasher.hash(&items[2]);
-        let hash_3 = hasher.hash(&items[3]);
+        let hash_0 = hasher.hash_leaf(&items[0]);
+        let hash_1 = hasher.hash_leaf(&items[1]);
+        let hash_2 = hasher.hash_leaf(&items[2]);
+        let hash_3 = hasher.hash_leaf(&items[3]);
         let hash_01 = hasher.concat_hashes(hash_0, hash_1);
         let hash_23 = hasher.concat_hashes(hash_2, hash_3);
         let hash_03 = hasher.concat_hashes(hash_01, hash_23);
 
-        let hash_4 = hasher.hash(&items[4]);
-        let hash_5 = hasher.hash(&items[5]);
-        let hash_6 = hasher.hash(&items[6]);
-        let hash_7 = hasher.hash(&items[7]);
+        let hash_4 = hasher.hash_leaf(&items[4]);
+        let hash_5 = hasher.hash_leaf(&items[5]);
+        let hash_6 = hasher.hash_leaf(&items[6]);
+        let hash_7 = hasher.hash_leaf(&items[7]);
         let hash_45 = hasher.concat_hashes(hash_4, hash_5);
         let hash_67 = hasher.concat_hashes(hash_6, hash_7);
         let hash_47 = hasher.concat_hashes(hash_45, hash_67);
@@ -244,4 +655,243 @@ mod tests {
         assert!(proof_6.validate(&items[6], &hasher));
         assert!(!proof_6.validate("foo", &hasher));
     }
+
+    #[test]
+    fn test_second_preimage() {
+        let hasher = Sha3Hasher;
+
+        let one = "one";
+        let two = "two";
+        let tree = MerkleTree::new(&[one, two], hasher);
+
+        // The internal node is `concat_hashes(hash_leaf(one), hash_leaf(two))`. Its preimage
+        // (the concatenated child hashes) must not validate as a leaf, since leaves carry the
+        // `LEAF_PREFIX` while internal nodes carry the `NODE_PREFIX`.
+        let one_hash = hasher.hash_leaf(one);
+        let two_hash = hasher.hash_leaf(two);
+        let mut internal_preimage = Vec::with_capacity(2 * 32);
+        internal_preimage.extend(one_hash.as_ref());
+        internal_preimage.extend(two_hash.as_ref());
+
+        // A single-leaf tree over the preimage would have that leaf as its root; domain
+        // separation ensures it can never collide with the real (internal) root.
+        assert_ne!(hasher.hash_leaf(&internal_preimage), tree.root());
+        assert_eq!(
+            hasher.concat_hashes(one_hash, two_hash),
+            tree.root()
+        );
+    }
+
+    #[test]
+    fn test_incremental_empty_root() {
+        let hasher = Sha3Hasher;
+        let tree = IncrementalMerkleTree::new(3, hasher);
+
+        // The root of the empty tree is the depth-3 zero hash.
+        let zero_0 = hasher.hash_leaf([]);
+        let zero_1 = hasher.concat_hashes(zero_0, zero_0);
+        let zero_2 = hasher.concat_hashes(zero_1, zero_1);
+        let zero_3 = hasher.concat_hashes(zero_2, zero_2);
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), zero_3);
+    }
+
+    #[test]
+    fn test_incremental_root_matches_manual() {
+        let hasher = Sha3Hasher;
+        let mut tree = IncrementalMerkleTree::new(2, hasher);
+
+        tree.append("zero", &hasher);
+        tree.append("one", &hasher);
+        tree.append("two", &hasher);
+        assert_eq!(tree.len(), 3);
+
+        // Three of four leaves filled: the last leaf is the depth-0 zero hash.
+        let hash_0 = hasher.hash_leaf("zero");
+        let hash_1 = hasher.hash_leaf("one");
+        let hash_2 = hasher.hash_leaf("two");
+        let hash_3 = hasher.hash_leaf([]);
+        let hash_01 = hasher.concat_hashes(hash_0, hash_1);
+        let hash_23 = hasher.concat_hashes(hash_2, hash_3);
+        assert_eq!(tree.root(), hasher.concat_hashes(hash_01, hash_23));
+    }
+
+    #[test]
+    fn test_incremental_witness_path() {
+        let hasher = Sha3Hasher;
+        let mut tree = IncrementalMerkleTree::new(3, hasher);
+
+        // Witness the third leaf, then fill the remaining leaves.
+        tree.append("a", &hasher);
+        tree.append("b", &hasher);
+        tree.append("c", &hasher);
+        let id = tree.witness();
+        assert_eq!(id, 2);
+        for item in ["d", "e", "f", "g", "h"] {
+            tree.append(item, &hasher);
+        }
+
+        let proof = tree.path(id);
+        assert_eq!(proof.root, tree.root());
+        assert!(proof.validate("c", &hasher));
+        assert!(!proof.validate("x", &hasher));
+
+        tree.remove_witness(id);
+    }
+
+    #[test]
+    fn test_update_leaf() {
+        let hasher = Sha3Hasher;
+
+        let mut items = (0..8).map(|n| n.to_string()).collect::<Vec<_>>();
+        let mut tree = MerkleTree::new(&items, hasher);
+
+        // Updating a leaf must yield the same tree as a full rebuild over the changed items.
+        items[5] = "changed".to_string();
+        let expected = MerkleTree::new(&items, hasher);
+
+        let root = tree.update_leaf(5, "changed", &hasher);
+        assert_eq!(root, expected.root());
+        assert_eq!(tree.root(), expected.root());
+
+        // Proofs over the mutated tree still validate against the new root.
+        let proof = tree.proof(5);
+        assert!(proof.validate("changed", &hasher));
+        assert!(!proof.validate("5", &hasher));
+    }
+
+    #[test]
+    fn test_update_leaf_unbalanced() {
+        let hasher = Sha3Hasher;
+
+        let mut items = (0..5).map(|n| n.to_string()).collect::<Vec<_>>();
+        let mut tree = MerkleTree::new_unbalanced(&items, hasher);
+
+        // Updating the promoted trailing leaf must match a full unbalanced rebuild.
+        items[4] = "changed".to_string();
+        let expected = MerkleTree::new_unbalanced(&items, hasher);
+
+        let root = tree.update_leaf(4, "changed", &hasher);
+        assert_eq!(root, expected.root());
+
+        let proof = tree.proof(4);
+        assert!(proof.validate("changed", &hasher));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_leaf_padded_rejected() {
+        let hasher = Sha3Hasher;
+
+        // Three items pad up to four leaves; update_leaf must refuse the padded tree rather
+        // than leave the duplicated last leaf stale.
+        let mut tree = MerkleTree::new(&["one", "two", "three"], hasher);
+        tree.update_leaf(2, "three2", &hasher);
+    }
+
+    #[test]
+    fn test_sparse_membership() {
+        let hasher = Sha3Hasher;
+        let mut tree = SparseMerkleTree::new(hasher);
+
+        tree.insert("alice", "1", &hasher);
+        tree.insert("bob", "2", &hasher);
+
+        let proof = tree.proof("alice", &hasher);
+        assert!(proof.validate("1", &hasher));
+        // Wrong value for a present key must not validate.
+        assert!(!proof.validate("2", &hasher));
+
+        // Overwriting a key updates its value.
+        tree.insert("alice", "3", &hasher);
+        let proof = tree.proof("alice", &hasher);
+        assert!(proof.validate("3", &hasher));
+    }
+
+    #[test]
+    fn test_sparse_non_membership() {
+        let hasher = Sha3Hasher;
+        let mut tree = SparseMerkleTree::new(hasher);
+
+        tree.insert("alice", "1", &hasher);
+
+        // An absent key resolves to the empty leaf, so the non-membership proof validates
+        // against an empty item.
+        let proof = tree.proof("carol", &hasher);
+        assert!(proof.validate(b"", &hasher));
+        // ...and a present key does not resolve to the empty leaf.
+        let proof = tree.proof("alice", &hasher);
+        assert!(!proof.validate(b"", &hasher));
+    }
+
+    #[test]
+    fn test_new_unbalanced_three() {
+        let hasher = Sha3Hasher;
+
+        let one = "one";
+        let two = "two";
+        let three = "three";
+        let one_hash = hasher.hash_leaf(one);
+        let two_hash = hasher.hash_leaf(two);
+        let three_hash = hasher.hash_leaf(three);
+        let tree = MerkleTree::new_unbalanced(&[one, two, three], hasher);
+
+        // The lone third leaf is promoted unchanged instead of being duplicated.
+        let expected_root_hash = {
+            let one_two_hash = hasher.concat_hashes(one_hash, two_hash);
+            hasher.concat_hashes(one_two_hash, three_hash)
+        };
+        assert_eq!(tree.root(), expected_root_hash);
+    }
+
+    #[test]
+    fn test_unbalanced_proof() {
+        let hasher = Sha3Hasher;
+
+        let items = (0..5).map(|n| n.to_string()).collect::<Vec<_>>();
+        let tree = MerkleTree::new_unbalanced(&items, hasher);
+
+        // Every leaf must have a proof that validates, including the promoted trailing leaf
+        // whose path skips the sibling-less promotion step.
+        for (index, item) in items.iter().enumerate() {
+            let proof = tree.proof(index);
+            assert!(proof.validate(item, &hasher), "leaf {index} must validate");
+            assert!(!proof.validate("nope", &hasher));
+        }
+    }
+
+    #[test]
+    fn test_unbalanced_matches_balanced_for_power_of_two() {
+        let hasher = Sha3Hasher;
+
+        let items = (0..8).map(|n| n.to_string()).collect::<Vec<_>>();
+        let balanced = MerkleTree::new(&items, hasher);
+        let unbalanced = MerkleTree::new_unbalanced(&items, hasher);
+
+        assert_eq!(balanced.root(), unbalanced.root());
+        assert_eq!(balanced.proof(6), unbalanced.proof(6));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_proof_serde_round_trip() {
+        let hasher = Sha3Hasher;
+
+        let items = (0..8).map(|n| n.to_string()).collect::<Vec<_>>();
+        let tree = MerkleTree::new(&items, hasher);
+        let proof = tree.proof(6);
+
+        // JSON (human-readable): hashes are encoded as `0x…` hex strings.
+        let json = serde_json::to_string(&proof).unwrap();
+        assert!(json.contains("0x"));
+        let from_json = serde_json::from_str::<MerkleProof<32>>(&json).unwrap();
+        assert_eq!(from_json, proof);
+        assert!(from_json.validate(&items[6], &hasher));
+
+        // Compact binary: hashes are encoded as raw bytes.
+        let bytes = bincode::serialize(&proof).unwrap();
+        let from_bytes = bincode::deserialize::<MerkleProof<32>>(&bytes).unwrap();
+        assert_eq!(from_bytes, proof);
+        assert!(from_bytes.validate(&items[6], &hasher));
+    }
 }